@@ -5,5 +5,7 @@ pub enum ScreenError {
 	#[error("Error writing data to screen")]
 	WriteError,
 	#[error("Wrong image size; must be 320x480 or 480x320")]
-	WrongImageSize
+	WrongImageSize,
+	#[error("Region is out of bounds of the panel")]
+	RegionOutOfBounds
 }
\ No newline at end of file
@@ -0,0 +1,197 @@
+use image::{ImageBuffer, Rgb};
+
+use crate::errors::ScreenError;
+use crate::screen::Screen;
+
+/// Wraps a [`Screen`] and keeps the last presented frame in memory, so that
+/// [`FrameBuffer::present`] can diff incoming frames and transmit only the
+/// rectangles that actually changed instead of resending the whole panel.
+pub struct FrameBuffer {
+	screen: Screen,
+	width: u16,
+	height: u16,
+	previous: Option<Vec<u16>>,
+}
+
+impl FrameBuffer {
+	/// Wraps `screen`. `width`/`height` must match the resolution of the images
+	/// passed to [`FrameBuffer::present`].
+	pub fn new(screen: Screen, width: u16, height: u16) -> FrameBuffer {
+		FrameBuffer {
+			screen,
+			width,
+			height,
+			previous: None,
+		}
+	}
+
+	/// Presents `img`, sending only the rows that changed since the last call.
+	/// Pass `force = true` to bypass diffing and repaint the whole panel, e.g.
+	/// after the screen has just been cleared or powered on.
+	pub fn present(&mut self, img: &ImageBuffer<Rgb<u8>, Vec<u8>>, force: bool) -> Result<(), ScreenError> {
+		let width = self.width;
+		let height = self.height;
+
+		if img.width() != width as u32 || img.height() != height as u32 {
+			return Err(ScreenError::WrongImageSize);
+		}
+
+		let frame = to_rgb565_words(img);
+		let previous = if force { None } else { self.previous.as_deref() };
+
+		for (x, y, band_width, band_height) in dirty_rectangles(previous, &frame, width, height) {
+			let band = ImageBuffer::from_fn(u32::from(band_width), u32::from(band_height), |bx, by| {
+				*img.get_pixel(u32::from(x) + bx, u32::from(y) + by)
+			});
+
+			self.screen.draw_region(x, y, &band)?;
+		}
+
+		self.previous = Some(frame);
+
+		Ok(())
+	}
+}
+
+fn to_rgb565_words(img: &ImageBuffer<Rgb<u8>, Vec<u8>>) -> Vec<u16> {
+	img.pixels().map(|pixel| crate::screen::rgb565_word(*pixel)).collect()
+}
+
+/// Diffs `frame` against `previous` row by row, coalesces consecutive changed
+/// rows into bands, and tightens each band to its changed column range.
+/// Returns each band as `(x, y, width, height)`. Pure and hardware-free, so the
+/// banding algorithm that drives [`FrameBuffer::present`] can be unit tested
+/// without a real panel.
+fn dirty_rectangles(previous: Option<&[u16]>, frame: &[u16], width: u16, height: u16) -> Vec<(u16, u16, u16, u16)> {
+	let mut rects = Vec::new();
+	let mut row = 0u16;
+
+	while row < height {
+		if !row_changed(previous, frame, width, row) {
+			row += 1;
+			continue;
+		}
+
+		let start_row = row;
+		while row < height && row_changed(previous, frame, width, row) {
+			row += 1;
+		}
+		let end_row = row;
+
+		let (min_col, max_col) = band_column_range(previous, frame, width, start_row, end_row);
+		rects.push((min_col, start_row, max_col - min_col + 1, end_row - start_row));
+	}
+
+	rects
+}
+
+fn row_changed(previous: Option<&[u16]>, frame: &[u16], width: u16, row: u16) -> bool {
+	let start = row as usize * width as usize;
+	let end = start + width as usize;
+	match previous {
+		Some(previous) => previous[start..end] != frame[start..end],
+		None => true,
+	}
+}
+
+fn band_column_range(previous: Option<&[u16]>, frame: &[u16], width: u16, start_row: u16, end_row: u16) -> (u16, u16) {
+	let mut min_col = width - 1;
+	let mut max_col = 0;
+
+	for row in start_row..end_row {
+		let offset = row as usize * width as usize;
+		for col in 0..width {
+			let idx = offset + col as usize;
+			let changed = match previous {
+				Some(previous) => previous[idx] != frame[idx],
+				None => true,
+			};
+			if changed {
+				min_col = min_col.min(col);
+				max_col = max_col.max(col);
+			}
+		}
+	}
+
+	(min_col, max_col)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn row_changed_detects_differences() {
+		let width = 4;
+		let previous = vec![1, 2, 3, 4, 5, 6, 7, 8];
+		let same = vec![1, 2, 3, 4, 5, 6, 7, 8];
+		let different = vec![1, 2, 3, 4, 0, 6, 7, 8];
+
+		assert!(!row_changed(Some(&previous), &same, width, 0));
+		assert!(!row_changed(Some(&previous), &same, width, 1));
+		assert!(!row_changed(Some(&previous), &different, width, 0));
+		assert!(row_changed(Some(&previous), &different, width, 1));
+	}
+
+	#[test]
+	fn row_changed_with_no_previous_is_always_changed() {
+		let frame = vec![0, 0, 0, 0];
+		assert!(row_changed(None, &frame, 4, 0));
+	}
+
+	#[test]
+	fn band_column_range_tightens_to_changed_columns() {
+		let width = 5;
+		// Row 0 unchanged, row 1 has columns 1..=3 changed.
+		let previous = vec![0, 0, 0, 0, 0, 0, 9, 9, 9, 0];
+		let new = vec![0, 0, 0, 0, 0, 0, 1, 2, 3, 0];
+
+		let range = band_column_range(Some(&previous), &new, width, 1, 2);
+		assert_eq!(range, (1, 3));
+	}
+
+	#[test]
+	fn dirty_rectangles_coalesces_consecutive_rows_into_one_band() {
+		let width = 3;
+		let height = 4;
+		let previous = vec![0u16; width as usize * height as usize];
+		let mut new = previous.clone();
+		new[width as usize + 2] = 1;
+		new[2 * width as usize + 2] = 1;
+
+		let rects = dirty_rectangles(Some(&previous), &new, width, height);
+		assert_eq!(rects, vec![(2, 1, 1, 2)]);
+	}
+
+	#[test]
+	fn dirty_rectangles_produces_disjoint_bands_for_non_adjacent_rows() {
+		let width = 3;
+		let height = 5;
+		let previous = vec![0u16; width as usize * height as usize];
+		let mut new = previous.clone();
+		new[0] = 1; // row 0, col 0
+		new[4 * width as usize] = 1; // row 4, col 0
+
+		let rects = dirty_rectangles(Some(&previous), &new, width, height);
+		assert_eq!(rects, vec![(0, 0, 1, 1), (0, 4, 1, 1)]);
+	}
+
+	#[test]
+	fn dirty_rectangles_is_empty_for_unchanged_frame() {
+		let width = 3;
+		let height = 3;
+		let frame = vec![7u16; width as usize * height as usize];
+
+		assert!(dirty_rectangles(Some(&frame), &frame, width, height).is_empty());
+	}
+
+	#[test]
+	fn dirty_rectangles_covers_everything_with_no_previous_frame() {
+		let width = 2;
+		let height = 2;
+		let frame = vec![0u16; width as usize * height as usize];
+
+		let rects = dirty_rectangles(None, &frame, width, height);
+		assert_eq!(rects, vec![(0, 0, width, height)]);
+	}
+}
@@ -1,5 +1,7 @@
 pub mod errors;
+pub mod framebuffer;
 pub mod screen;
+pub mod text;
 
 #[cfg(test)]
 mod tests {
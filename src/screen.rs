@@ -1,3 +1,11 @@
+use embedded_graphics::{
+	draw_target::DrawTarget,
+	geometry::{OriginDimensions, Size},
+	pixelcolor::{raw::RawU16, Rgb565},
+	prelude::*,
+	primitives::Rectangle,
+	Pixel,
+};
 use image::ImageBuffer;
 use serialport::SerialPort;
 
@@ -6,6 +14,7 @@ pub const HEIGHT: u16 = 480;
 const SCREEN_SERIAL: &str = "USB35INCHIPSV2";
 
 #[allow(dead_code)]
+#[derive(Clone, Copy)]
 pub enum Orientation {
 	Portrait = 0,
 	ReversePortrait = 1,
@@ -26,7 +35,9 @@ pub enum ScreenCommand {
 }
 
 pub struct Screen {
-	port: Box<dyn SerialPort>
+	port: Box<dyn SerialPort>,
+	orientation: Orientation,
+	invert: bool
 }
 
 impl Screen {
@@ -57,7 +68,7 @@ impl Screen {
 		let port = serialport::new(&port_name, 115_200)
 			.timeout(std::time::Duration::from_secs(1))
 			.open()?;
-		Ok(Screen { port })
+		Ok(Screen { port, orientation: Orientation::Portrait, invert: false })
 	}
 }
 
@@ -83,21 +94,37 @@ impl Screen {
 
 		self.port.write(&bytes).map_err(|_| crate::errors::ScreenError::WriteError)?;
 
+		self.orientation = orientation;
+
 		Ok(())
 	}
 
+	#[allow(unused)]
+	/// Returns the panel's current resolution as `(width, height)`, adjusted for the
+	/// active [`Orientation`] set via [`Screen::orientation`].
+	pub fn get_resolution(&self) -> (u16, u16) {
+		match self.orientation {
+			Orientation::Portrait | Orientation::ReversePortrait => (WIDTH, HEIGHT),
+			Orientation::Landscape | Orientation::ReverseLandscape => (HEIGHT, WIDTH),
+		}
+	}
+
 	#[allow(unused)]
 	/// Clears the screen to white.
-	/// Does not work correctly in landscape mode, switch to Portrait mode before using this function.
+	/// Addresses the full panel at the current [`Orientation`] (see [`Screen::get_resolution`]),
+	/// so this works correctly regardless of orientation.
 	pub fn clear(&mut self) -> Result<(), crate::errors::ScreenError> {
-		self.send_command(0, 0, 0, 0, ScreenCommand::Clear)
+		let (width, height) = self.get_resolution();
+		self.send_command(0, 0, width - 1, height - 1, ScreenCommand::Clear)
 	}
 
 	#[allow(unused)]
 	/// Clears the screen to black.
-	/// Does not work correctly in landscape mode, switch to Portrait mode before using this function.
+	/// Addresses the full panel at the current [`Orientation`] (see [`Screen::get_resolution`]),
+	/// so this works correctly regardless of orientation.
 	pub fn to_black(&mut self) -> Result<(), crate::errors::ScreenError> {
-		self.send_command(0, 0, 0, 0, ScreenCommand::ToBlack)
+		let (width, height) = self.get_resolution();
+		self.send_command(0, 0, width - 1, height - 1, ScreenCommand::ToBlack)
 	}
 
 	#[allow(unused)]
@@ -137,51 +164,212 @@ impl Screen {
 
 	#[allow(unused)]
 	/// Draws an `ImageBuffer` to the screen.
-	/// The image must be 320x480 or 480x320. Although not checked, the orientation of the image should match the orientation of the screen.
-	/// Otherwise the screen will still interpret the image as if it were in the wrong orientation, part of the image may be cut off and the screen will wrap around to the start in rendering.
+	/// The image must exactly match the panel's current resolution (see
+	/// [`Screen::get_resolution`]), which depends on the active [`Orientation`].
 	pub fn draw(&mut self, img: ImageBuffer<image::Rgb<u8>, Vec<u8>>) -> Result<(), crate::errors::ScreenError> {
-		if !((img.width() == WIDTH.into() || img.height() == HEIGHT.into()) || (img.width() == HEIGHT.into() || img.height() == WIDTH.into())) {
-			// panic!("Canvas size must be 320x480 or 480x320");
+		let (width, height) = self.get_resolution();
+		if img.width() != u32::from(width) || img.height() != u32::from(height) {
 			return Err(crate::errors::ScreenError::WrongImageSize);
 		}
 
-		let width = img.width();
-		let height = img.height();
-
 		// Set the display region
-		self.send_command(0, 0, (width - 1) as u16, (height - 1) as u16, ScreenCommand::DisplayBitmap)?;
+		self.send_command(0, 0, width - 1, height - 1, ScreenCommand::DisplayBitmap)?;
+
+		self.write_pixel_data(&img)
+	}
+
+	#[allow(unused)]
+	/// Draws `img` into the sub-rectangle of the panel starting at `(x, y)`, leaving
+	/// the rest of the panel untouched. Streams only the pixels in `img`, so it's much
+	/// cheaper than [`Screen::draw`] for updating a small widget like a clock or a gauge.
+	pub fn draw_region(&mut self, x: u16, y: u16, img: &ImageBuffer<image::Rgb<u8>, Vec<u8>>) -> Result<(), crate::errors::ScreenError> {
+		let width = img.width() as u16;
+		let height = img.height() as u16;
+		let (panel_width, panel_height) = self.get_resolution();
+
+		if u32::from(x) + u32::from(width) > u32::from(panel_width) || u32::from(y) + u32::from(height) > u32::from(panel_height) {
+			return Err(crate::errors::ScreenError::RegionOutOfBounds);
+		}
+
+		self.send_command(x, y, x + width - 1, y + height - 1, ScreenCommand::DisplayBitmap)?;
+
+		self.write_pixel_data(img)
+	}
+
+	#[allow(unused)]
+	/// Scales `img` to fit the active resolution (see [`Screen::get_resolution`]) while
+	/// preserving its aspect ratio, padding any leftover margin with black bars, then draws it.
+	pub fn draw_fit(&mut self, img: &ImageBuffer<image::Rgb<u8>, Vec<u8>>) -> Result<(), crate::errors::ScreenError> {
+		let (target_width, target_height) = self.get_resolution();
 
-		let pixels: Vec<_> = img.pixels().collect();
-		let width = width as usize;
+		let scale = (target_width as f32 / img.width() as f32).min(target_height as f32 / img.height() as f32);
+		let scaled_width = ((img.width() as f32 * scale).round() as u32).max(1);
+		let scaled_height = ((img.height() as f32 * scale).round() as u32).max(1);
 
-		for (i, chunk) in pixels.chunks_exact(width * 8).enumerate() {
+		let resized = image::imageops::resize(img, scaled_width, scaled_height, image::imageops::FilterType::Triangle);
+
+		let mut canvas = ImageBuffer::from_pixel(target_width as u32, target_height as u32, image::Rgb([0, 0, 0]));
+		let x_offset = ((target_width as u32 - scaled_width) / 2) as i64;
+		let y_offset = ((target_height as u32 - scaled_height) / 2) as i64;
+		image::imageops::overlay(&mut canvas, &resized, x_offset, y_offset);
+
+		self.draw(canvas)
+	}
+
+	/// Streams the pixels of `img` to the panel, assuming the display window has
+	/// already been set via `send_command`. Shared by [`Screen::draw`] and
+	/// [`Screen::draw_region`].
+	fn write_pixel_data(&mut self, img: &ImageBuffer<image::Rgb<u8>, Vec<u8>>) -> Result<(), crate::errors::ScreenError> {
+		let width = img.width() as usize;
+		let invert = self.invert;
+
+		for chunk in img.pixels().collect::<Vec<_>>().chunks(width * 8) {
 			let mut bytes: Vec<u8> = Vec::with_capacity(chunk.len() * 2);
 			for pixel in chunk {
-				let r = (pixel[0] >> 3) as u16;
-				let g = (pixel[1] >> 2) as u16;
-				let b = (pixel[2] >> 3) as u16;
-				let rgb565 = (r << 11) | (g << 5) | b;
-				bytes.push((rgb565 & 0xFF) as u8); // LSB
-				bytes.push((rgb565 >> 8) as u8); // MSB
+				bytes.extend_from_slice(&word_bytes(rgb565_word(*pixel), invert));
 			}
 			self.port.write(&bytes).map_err(|_| crate::errors::ScreenError::WriteError)?;
 		}
 
-		// Write the remaining pixels if any
-		let remainder = pixels.chunks_exact(width * 8).remainder();
-		if !remainder.is_empty() {
-			let mut bytes: Vec<u8> = Vec::with_capacity(remainder.len() * 2);
-			for pixel in remainder {
-				let r = (pixel[0] >> 3) as u16;
-				let g = (pixel[1] >> 2) as u16;
-				let b = (pixel[2] >> 3) as u16;
-				let rgb565 = (r << 11) | (g << 5) | b;
-				bytes.push((rgb565 & 0xFF) as u8); // LSB
-				bytes.push((rgb565 >> 8) as u8); // MSB
+		Ok(())
+	}
+
+	/// Converts an `embedded-graphics` color into the LSB/MSB RGB565 byte pair
+	/// the panel expects, i.e. the same encoding used by [`Screen::draw`], applying
+	/// [`DisplayControl::set_invert`] if it's active.
+	fn rgb565_bytes(&self, color: Rgb565) -> [u8; 2] {
+		word_bytes(RawU16::from(color).into_inner(), self.invert)
+	}
+}
+
+/// Converts an 8-bit `image::Rgb` pixel into its packed RGB565 word, by truncating
+/// each channel (`r>>3`/`g>>2`/`b>>3`) into an `embedded-graphics` `Rgb565` so the
+/// bit-shifts live in one place instead of being hand-rolled again at every call site.
+pub(crate) fn rgb565_word(pixel: image::Rgb<u8>) -> u16 {
+	let color = Rgb565::new(pixel.0[0] >> 3, pixel.0[1] >> 2, pixel.0[2] >> 3);
+	RawU16::from(color).into_inner()
+}
+
+/// Splits a 16-bit RGB565 word into the LSB/MSB byte pair the panel expects,
+/// XOR-ing it first when `invert` is set to emulate a night-mode display.
+fn word_bytes(word: u16, invert: bool) -> [u8; 2] {
+	let word = if invert { !word } else { word };
+	[(word & 0xFF) as u8, (word >> 8) as u8]
+}
+
+impl OriginDimensions for Screen {
+	fn size(&self) -> Size {
+		let (width, height) = self.get_resolution();
+		Size::new(width as u32, height as u32)
+	}
+}
+
+impl DrawTarget for Screen {
+	type Color = Rgb565;
+	type Error = crate::errors::ScreenError;
+
+	fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+	where
+		I: IntoIterator<Item = Pixel<Self::Color>>,
+	{
+		let (width, height) = self.get_resolution();
+
+		for Pixel(point, color) in pixels {
+			if point.x < 0 || point.y < 0 || point.x as u16 >= width || point.y as u16 >= height {
+				continue;
 			}
+
+			let x = point.x as u16;
+			let y = point.y as u16;
+
+			self.send_command(x, y, x, y, ScreenCommand::DisplayBitmap)?;
+			let bytes = self.rgb565_bytes(color);
 			self.port.write(&bytes).map_err(|_| crate::errors::ScreenError::WriteError)?;
 		}
 
 		Ok(())
 	}
+
+	fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+	where
+		I: IntoIterator<Item = Self::Color>,
+	{
+		let (width, height) = self.get_resolution();
+		let panel = Rectangle::new(Point::zero(), Size::new(width as u32, height as u32));
+		let clipped = area.intersection(&panel);
+
+		if clipped.size.width == 0 || clipped.size.height == 0 {
+			return Ok(());
+		}
+
+		let x = clipped.top_left.x as u16;
+		let y = clipped.top_left.y as u16;
+		let ex = x + clipped.size.width as u16 - 1;
+		let ey = y + clipped.size.height as u16 - 1;
+
+		self.send_command(x, y, ex, ey, ScreenCommand::DisplayBitmap)?;
+
+		// `colors` is in the same row-major order as `area.points()`; only keep the
+		// pixels that fall inside the panel so the stream lines up with `clipped`.
+		let clipped_right = clipped.top_left.x + clipped.size.width as i32;
+		let clipped_bottom = clipped.top_left.y + clipped.size.height as i32;
+
+		let mut bytes: Vec<u8> = Vec::with_capacity(clipped.size.width as usize * clipped.size.height as usize * 2);
+		for (point, color) in area.points().zip(colors) {
+			if point.x < clipped.top_left.x || point.x >= clipped_right || point.y < clipped.top_left.y || point.y >= clipped_bottom {
+				continue;
+			}
+			bytes.extend_from_slice(&self.rgb565_bytes(color));
+		}
+		self.port.write(&bytes).map_err(|_| crate::errors::ScreenError::WriteError)?;
+
+		Ok(())
+	}
+}
+
+/// A hardware-control surface modeled on Tock OS's `Screen` HIL, covering the
+/// operations common to display panels: power, rotation, brightness, and
+/// (emulated, where the firmware lacks it) color inversion. Implementing this
+/// trait for other Turing/XuanFang panel revisions lets callers target them
+/// generically instead of depending on this crate's concrete `Screen` type.
+pub trait DisplayControl {
+	/// Turns the panel on or off. While off, the screen stays powered and
+	/// retains the last image drawn.
+	fn set_power(&mut self, on: bool) -> Result<(), crate::errors::ScreenError>;
+
+	/// Sets the panel's rotation.
+	fn set_rotation(&mut self, orientation: Orientation) -> Result<(), crate::errors::ScreenError>;
+
+	/// Sets the brightness level, 0 (darkest) to 255 (brightest).
+	fn set_brightness(&mut self, level: u16) -> Result<(), crate::errors::ScreenError>;
+
+	/// Inverts the panel's colors, e.g. for a night-mode display. The firmware has
+	/// no native invert command, so this is emulated by XOR-ing the RGB565 words
+	/// of every frame before they're transmitted.
+	fn set_invert(&mut self, invert: bool) -> Result<(), crate::errors::ScreenError>;
+}
+
+impl DisplayControl for Screen {
+	fn set_power(&mut self, on: bool) -> Result<(), crate::errors::ScreenError> {
+		if on {
+			self.screen_on()
+		} else {
+			self.screen_off()
+		}
+	}
+
+	fn set_rotation(&mut self, orientation: Orientation) -> Result<(), crate::errors::ScreenError> {
+		self.orientation(orientation)
+	}
+
+	fn set_brightness(&mut self, level: u16) -> Result<(), crate::errors::ScreenError> {
+		// `Screen::brightness` uses the opposite convention (0 brightest, 255 darkest); invert here
+		// so this trait method's own documented contract (0 darkest, 255 brightest) actually holds.
+		self.brightness(255 - level.min(255) as u8)
+	}
+
+	fn set_invert(&mut self, invert: bool) -> Result<(), crate::errors::ScreenError> {
+		self.invert = invert;
+		Ok(())
+	}
 }
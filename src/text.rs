@@ -0,0 +1,145 @@
+use ab_glyph::{Font, FontRef, Glyph, InvalidFont, Point, PxScale, ScaleFont};
+use image::{ImageBuffer, Rgb};
+
+use crate::errors::ScreenError;
+use crate::screen::Screen;
+
+/// Rasterizes text with `ab_glyph` and blits the result onto a [`Screen`]
+/// through [`Screen::draw_region`], so only the rectangle covering the text
+/// is sent over the wire.
+pub struct TextRenderer<'a> {
+	font: FontRef<'a>,
+}
+
+impl<'a> TextRenderer<'a> {
+	/// Loads a font from raw TrueType/OpenType bytes.
+	pub fn new(font_data: &'a [u8]) -> Result<TextRenderer<'a>, InvalidFont> {
+		Ok(TextRenderer {
+			font: FontRef::try_from_slice(font_data)?,
+		})
+	}
+}
+
+impl Screen {
+	#[allow(unused)]
+	/// Draws `text` at `(x, y)` using `renderer`, filling the background with `bg`
+	/// and the glyphs with `fg`, then pushes just that region to the panel.
+	///
+	/// The filled region spans the full remaining panel width (not just the
+	/// measured width of `text`), so drawing shorter text over a previous, wider
+	/// line clears the old glyphs instead of leaving stale fragments behind.
+	pub fn draw_text(
+		&mut self,
+		x: u16,
+		y: u16,
+		text: &str,
+		renderer: &TextRenderer,
+		size: f32,
+		fg: Rgb<u8>,
+		bg: Rgb<u8>,
+	) -> Result<(), ScreenError> {
+		let (panel_width, _) = self.get_resolution();
+		let width = panel_width.saturating_sub(x).max(1);
+		let img = rasterize(renderer, text, size, fg, bg, width);
+		self.draw_region(x, y, &img)
+	}
+}
+
+/// Lays out `text` at `size` and rasterizes it into an `ImageBuffer` that is
+/// `width` pixels wide (so a full line can be cleared, not just the glyphs),
+/// filled with `bg` and the glyphs in `fg`.
+fn rasterize(renderer: &TextRenderer, text: &str, size: f32, fg: Rgb<u8>, bg: Rgb<u8>, width: u16) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+	let scale = PxScale::from(size);
+	let scaled_font = renderer.font.as_scaled(scale);
+
+	let mut glyphs: Vec<Glyph> = Vec::new();
+	let mut cursor = 0.0f32;
+	for c in text.chars() {
+		let glyph_id = scaled_font.glyph_id(c);
+		glyphs.push(glyph_id.with_scale_and_position(scale, Point { x: cursor, y: scaled_font.ascent() }));
+		cursor += scaled_font.h_advance(glyph_id);
+	}
+
+	let width = width as u32;
+	let height = ((scaled_font.ascent() - scaled_font.descent()).ceil() as u32).max(1);
+
+	let mut img = ImageBuffer::from_pixel(width, height, bg);
+
+	for glyph in glyphs {
+		if let Some(outlined) = scaled_font.outline_glyph(glyph) {
+			let bounds = outlined.px_bounds();
+			outlined.draw(|gx, gy, coverage| {
+				if coverage <= 0.0 {
+					return;
+				}
+
+				let px = bounds.min.x as i32 + gx as i32;
+				let py = bounds.min.y as i32 + gy as i32;
+				if px < 0 || py < 0 || px as u32 >= width || py as u32 >= height {
+					return;
+				}
+
+				img.put_pixel(px as u32, py as u32, blend(bg, fg, coverage));
+			});
+		}
+	}
+
+	img
+}
+
+fn blend(bg: Rgb<u8>, fg: Rgb<u8>, coverage: f32) -> Rgb<u8> {
+	let coverage = coverage.clamp(0.0, 1.0);
+	Rgb([
+		(bg[0] as f32 + (fg[0] as f32 - bg[0] as f32) * coverage) as u8,
+		(bg[1] as f32 + (fg[1] as f32 - bg[1] as f32) * coverage) as u8,
+		(bg[2] as f32 + (fg[2] as f32 - bg[2] as f32) * coverage) as u8,
+	])
+}
+
+/// A scrolling line-buffer terminal mode, similar to `ssd1306`'s terminal
+/// mode: callers `println` status lines and `Terminal` takes care of the
+/// cursor position and scrolling the oldest line off once the panel is full.
+pub struct Terminal<'a> {
+	renderer: TextRenderer<'a>,
+	font_size: f32,
+	line_height: u16,
+	fg: Rgb<u8>,
+	bg: Rgb<u8>,
+	lines: Vec<String>,
+}
+
+impl<'a> Terminal<'a> {
+	/// Creates a terminal that renders with `renderer` at `font_size`. How many
+	/// lines fit is read from the screen's resolution on every [`Terminal::println`]
+	/// call, since the panel's height depends on its current [`crate::screen::Orientation`].
+	pub fn new(renderer: TextRenderer<'a>, font_size: f32, fg: Rgb<u8>, bg: Rgb<u8>) -> Terminal<'a> {
+		let line_height = font_size.ceil() as u16 + 2;
+		Terminal {
+			renderer,
+			font_size,
+			line_height,
+			fg,
+			bg,
+			lines: Vec::new(),
+		}
+	}
+
+	/// Appends `line`, scrolling the oldest line off the top if the panel is
+	/// already full, then redraws every visible line on `screen`.
+	pub fn println(&mut self, screen: &mut Screen, line: &str) -> Result<(), ScreenError> {
+		let (_, panel_height) = screen.get_resolution();
+		let max_lines = (panel_height / self.line_height).max(1);
+
+		self.lines.push(line.to_string());
+		if self.lines.len() > max_lines as usize {
+			let overflow = self.lines.len() - max_lines as usize;
+			self.lines.drain(0..overflow);
+		}
+
+		for (i, line) in self.lines.iter().enumerate() {
+			screen.draw_text(0, i as u16 * self.line_height, line, &self.renderer, self.font_size, self.fg, self.bg)?;
+		}
+
+		Ok(())
+	}
+}